@@ -3,9 +3,11 @@ extern crate syn;
 #[macro_use]
 extern crate quote;
 
+use std::collections::HashSet;
+
 use proc_macro::TokenStream;
 use quote::Tokens;
-use syn::{Body, Field, Ident, Ty, VariantData};
+use syn::{Attribute, Body, Field, Ident, Lit, MetaItem, NestedMetaItem, PathParameters, Ty, VariantData};
 
 #[proc_macro_derive(PacketVariable)]
 pub fn packet_variable_derive(input: TokenStream) -> TokenStream {
@@ -20,10 +22,10 @@ fn impl_packet_variable(ast: &syn::DeriveInput) -> Tokens {
 
     match &ast.body {
         Body::Struct(VariantData::Struct(fields)) => {
-            impl_struct_derive(name, fields)
+            impl_struct_derive(name, fields, parse_endian_attr(&ast.attrs))
         },
-        Body::Enum(data) => {
-            todo!()
+        Body::Enum(variants) => {
+            impl_enum_derive(name, variants)
         }
         _ => {
             panic!("Packet Variable arrive not supported for this type");
@@ -31,31 +33,528 @@ fn impl_packet_variable(ast: &syn::DeriveInput) -> Tokens {
     }
 }
 
-fn impl_struct_derive(name: &Ident, fields: &Vec<Field>) -> Tokens {
-    let from_idents = fields.iter().map(| f | &f.ident);
-    let to_idents = from_idents.clone();
-    let types = fields.iter().map(| f | &f.ty);
-    println!("{types:?}");
-    let types_clone = types.clone();
+/// A byte order chosen via `#[packet(endian = "...")]`.
+#[derive(Clone, Copy)]
+enum EndianChoice {
+    Big,
+    Little,
+    /// `endian = "default"` — resolved at runtime via `Endian::default_endian()`.
+    Runtime,
+}
+
+impl EndianChoice {
+    fn to_tokens(self) -> Tokens {
+        match self {
+            EndianChoice::Big => quote! { Endian::Big },
+            EndianChoice::Little => quote! { Endian::Little },
+            EndianChoice::Runtime => quote! { Endian::default_endian() },
+        }
+    }
+
+    fn from_str(value: &str) -> EndianChoice {
+        match value {
+            "be" | "big" => EndianChoice::Big,
+            "le" | "little" => EndianChoice::Little,
+            "default" => EndianChoice::Runtime,
+            other => panic!("Unsupported #[packet(endian = \"{other}\")]; expected \"be\", \"le\" or \"default\""),
+        }
+    }
+}
+
+fn parse_endian_attr(attrs: &[Attribute]) -> Option<EndianChoice> {
+    for attr in attrs {
+        let (list_name, items) = match &attr.value {
+            MetaItem::List(list_name, items) => (list_name, items),
+            _ => continue,
+        };
+
+        if !ident_named(list_name, "packet") {
+            continue;
+        }
+
+        for item in items {
+            if let NestedMetaItem::MetaItem(MetaItem::NameValue(key, Lit::Str(value, _))) = item {
+                if ident_named(key, "endian") {
+                    return Some(EndianChoice::from_str(value));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Parsed form of a field's `#[packet(...)]` helper attribute.
+///
+/// - `length = "u8"` — read/write the `Vec`/`String` length prefix as the named integer
+///   type instead of the default `LegacyLength`.
+/// - `skip` — the field never touches the wire; it is always `Default::default()`.
+/// - `count = N` — a `Vec<T>` field read as exactly `N` elements with no length prefix.
+/// - `if = "other_field"` — the field is only present on the wire when `other_field`
+///   (a previously declared field) is true. Must be paired with `default`, which supplies
+///   the `Default::default()` fallback used when the condition is false.
+/// - `default` — see `if` above.
+/// - `endian = "be" | "le" | "default"` — byte order for this field, overriding the struct-level
+///   `#[packet(endian = ...)]` (if any). Only supported on primitive integer/float fields and
+///   `String`, and can't be combined with `length`/`skip`/`count`/`if`.
+struct FieldAttrs {
+    length: Option<Ident>,
+    skip: bool,
+    default: bool,
+    count: Option<u64>,
+    cond: Option<Ident>,
+    endian: Option<EndianChoice>,
+}
+
+fn ident_named(ident: &Ident, name: &str) -> bool {
+    ident.to_string() == name
+}
+
+fn parse_field_attrs(field: &Field) -> FieldAttrs {
+    let mut parsed = FieldAttrs { length: None, skip: false, default: false, count: None, cond: None, endian: None };
+
+    for attr in &field.attrs {
+        let (list_name, items) = match &attr.value {
+            MetaItem::List(list_name, items) => (list_name, items),
+            _ => continue,
+        };
+
+        if !ident_named(list_name, "packet") {
+            continue;
+        }
+
+        for item in items {
+            match item {
+                NestedMetaItem::MetaItem(MetaItem::Word(word)) if ident_named(word, "skip") => {
+                    parsed.skip = true;
+                },
+                NestedMetaItem::MetaItem(MetaItem::Word(word)) if ident_named(word, "default") => {
+                    parsed.default = true;
+                },
+                NestedMetaItem::MetaItem(MetaItem::NameValue(key, Lit::Str(value, _))) if ident_named(key, "length") => {
+                    parsed.length = Some(Ident::new(value.clone()));
+                },
+                NestedMetaItem::MetaItem(MetaItem::NameValue(key, Lit::Str(value, _))) if ident_named(key, "if") => {
+                    parsed.cond = Some(Ident::new(value.clone()));
+                },
+                NestedMetaItem::MetaItem(MetaItem::NameValue(key, Lit::Str(value, _))) if ident_named(key, "endian") => {
+                    parsed.endian = Some(EndianChoice::from_str(value));
+                },
+                NestedMetaItem::MetaItem(MetaItem::NameValue(key, Lit::Int(value, _))) if ident_named(key, "count") => {
+                    parsed.count = Some(*value);
+                },
+                other => panic!("Unsupported #[packet(...)] attribute: {:?}", other),
+            }
+        }
+    }
+
+    if parsed.cond.is_some() && !parsed.default {
+        panic!("#[packet(if = \"...\")] must be paired with #[packet(default)] to supply a fallback value");
+    }
+
+    parsed
+}
+
+fn ty_path_last_segment(ty: &Ty) -> Option<&Ident> {
+    match ty {
+        Ty::Path(_, path) => path.segments.last().map(| segment | &segment.ident),
+        _ => None,
+    }
+}
+
+fn ty_is_vec(ty: &Ty) -> bool {
+    ty_path_last_segment(ty).map_or(false, | ident | ident_named(ident, "Vec"))
+}
+
+fn ty_is_string(ty: &Ty) -> bool {
+    ty_path_last_segment(ty).map_or(false, | ident | ident_named(ident, "String"))
+}
+
+fn vec_element_ty(ty: &Ty) -> &Ty {
+    match ty {
+        Ty::Path(_, path) => match &path.segments.last().unwrap().parameters {
+            PathParameters::AngleBracketed(params) => &params.types[0],
+            _ => panic!("Expected Vec<T> to carry a type parameter"),
+        },
+        _ => panic!("Expected a path type"),
+    }
+}
+
+fn field_read_expr(ty: &Ty, attrs: &FieldAttrs, use_reader: bool) -> Tokens {
+    if let Some(len_ty) = &attrs.length {
+        if ty_is_vec(ty) {
+            let elem_ty = vec_element_ty(ty);
+            if use_reader {
+                quote! {{
+                    let len: #len_ty = PacketVariable::try_from_packet(reader)?;
+                    let mut elements: Vec<#elem_ty> = Vec::new();
+                    for _ in 0..len {
+                        elements.push(PacketVariable::try_from_packet(reader)?);
+                    }
+                    elements
+                }}
+            } else {
+                quote! {{
+                    let len: #len_ty = packet.read();
+                    let mut elements: Vec<#elem_ty> = Vec::new();
+                    for _ in 0..len {
+                        elements.push(packet.read());
+                    }
+                    elements
+                }}
+            }
+        } else if ty_is_string(ty) {
+            if use_reader {
+                quote! {{
+                    let len: #len_ty = PacketVariable::try_from_packet(reader)?;
+                    let mut buf = vec![0u8; len as usize];
+                    reader.read_exact(&mut buf).map_err(| _ | PacketError::NotEnoughBytes)?;
+                    String::from_utf8(buf).map_err(| _ | PacketError::InvalidUtf8)?
+                }}
+            } else {
+                quote! {{
+                    let len: #len_ty = packet.read();
+                    let mut buf: Vec<u8> = Vec::new();
+                    for _ in 0..len {
+                        buf.push(packet.read());
+                    }
+                    String::from_utf8(buf).expect("Couldn't read string")
+                }}
+            }
+        } else {
+            panic!("#[packet(length = ...)] is only supported on Vec<T> and String fields");
+        }
+    } else if let Some(count) = attrs.count {
+        if !ty_is_vec(ty) {
+            panic!("#[packet(count = ...)] is only supported on Vec<T> fields");
+        }
+
+        if use_reader {
+            quote! {{
+                let mut elements = Vec::new();
+                for _ in 0..#count {
+                    elements.push(PacketVariable::try_from_packet(reader)?);
+                }
+                elements
+            }}
+        } else {
+            quote! {{
+                let mut elements = Vec::new();
+                for _ in 0..#count {
+                    elements.push(packet.read());
+                }
+                elements
+            }}
+        }
+    } else if use_reader {
+        quote! { PacketVariable::try_from_packet(reader)? }
+    } else {
+        quote! { packet.read() }
+    }
+}
+
+fn field_endian_binding_stmt(ident: &Ident, ty: &Ty, endian: EndianChoice, use_reader: bool) -> Tokens {
+    let endian_expr = endian.to_tokens();
+
+    if use_reader {
+        if ty_is_string(ty) {
+            quote! {
+                let #ident: #ty = {
+                    let mut len_buf = [0u8; 2];
+                    reader.read_exact(&mut len_buf).map_err(| _ | PacketError::NotEnoughBytes)?;
+                    let len = <u16 as Endianness>::from_packet_endian(len_buf.to_vec(), #endian_expr).0 as usize;
+                    let mut buf = vec![0u8; len];
+                    reader.read_exact(&mut buf).map_err(| _ | PacketError::NotEnoughBytes)?;
+                    String::from_utf8(buf).map_err(| _ | PacketError::InvalidUtf8)?
+                };
+            }
+        } else {
+            quote! {
+                let #ident: #ty = {
+                    let mut buf = vec![0u8; core::mem::size_of::<#ty>()];
+                    reader.read_exact(&mut buf).map_err(| _ | PacketError::NotEnoughBytes)?;
+                    <#ty as Endianness>::from_packet_endian(buf, #endian_expr).0
+                };
+            }
+        }
+    } else if ty_is_string(ty) {
+        quote! {
+            let #ident: #ty = {
+                let remaining = packet.get_bytes()[packet.read_index..].to_vec();
+                let (value, consumed) = <#ty as Endianness>::from_packet_endian(remaining, #endian_expr);
+                packet.read_index += consumed;
+                value
+            };
+        }
+    } else {
+        quote! {
+            let #ident: #ty = {
+                let len = core::mem::size_of::<#ty>();
+                let remaining = packet.get_bytes()[packet.read_index..packet.read_index + len].to_vec();
+                let (value, _) = <#ty as Endianness>::from_packet_endian(remaining, #endian_expr);
+                packet.read_index += len;
+                value
+            };
+        }
+    }
+}
+
+fn field_endian_write_stmt(ident: &Ident, ty: &Ty, endian: EndianChoice) -> Tokens {
+    let endian_expr = endian.to_tokens();
+    quote! {
+        packet.append(RawBytes(<#ty as Endianness>::to_packet_endian(&self.#ident, #endian_expr)));
+    }
+}
+
+fn field_binding_stmt(ident: &Ident, ty: &Ty, attrs: &FieldAttrs, use_reader: bool) -> Tokens {
+    if attrs.skip {
+        return quote! { let #ident: #ty = Default::default(); };
+    }
+
+    let read_expr = field_read_expr(ty, attrs, use_reader);
+
+    if let Some(cond) = &attrs.cond {
+        quote! {
+            let #ident: #ty = if #cond {
+                #read_expr
+            } else {
+                Default::default()
+            };
+        }
+    } else {
+        quote! {
+            let #ident: #ty = #read_expr;
+        }
+    }
+}
+
+fn field_write_stmt(ident: &Ident, ty: &Ty, attrs: &FieldAttrs) -> Tokens {
+    if attrs.skip {
+        return quote! {};
+    }
+
+    let write_expr = if let Some(len_ty) = &attrs.length {
+        if ty_is_vec(ty) {
+            quote! {{
+                let len: #len_ty = self.#ident.len() as #len_ty;
+                packet.append(len);
+                for element in self.#ident.iter() {
+                    packet.append(element.clone());
+                }
+            }}
+        } else if ty_is_string(ty) {
+            quote! {{
+                let bytes = self.#ident.as_bytes();
+                let len: #len_ty = bytes.len() as #len_ty;
+                packet.append(len);
+                for b in bytes {
+                    packet.append(*b);
+                }
+            }}
+        } else {
+            panic!("#[packet(length = ...)] is only supported on Vec<T> and String fields");
+        }
+    } else if attrs.count.is_some() {
+        quote! {
+            for element in self.#ident.iter() {
+                packet.append(element.clone());
+            }
+        }
+    } else {
+        quote! {
+            packet.append(self.#ident.clone());
+        }
+    };
+
+    if let Some(cond) = &attrs.cond {
+        quote! {
+            if self.#cond {
+                #write_expr
+            }
+        }
+    } else {
+        write_expr
+    }
+}
+
+fn field_size_stmt(ident: &Ident, ty: &Ty, attrs: &FieldAttrs, cond_sources: &HashSet<String>) -> Tokens {
+    if attrs.skip {
+        return quote! {};
+    }
+
+    if attrs.length.is_none() && attrs.count.is_none() && attrs.cond.is_none() && !cond_sources.contains(&ident.to_string()) {
+        return quote! {
+            {
+                let remaining = bytes[size..].to_vec();
+                if !<#ty as PacketVariable>::can_read(remaining.clone()) {
+                    return 0;
+                }
+                size += <#ty as PacketVariable>::read_size(remaining);
+            }
+        };
+    }
+
+    if cond_sources.contains(&ident.to_string()) {
+        return quote! {
+            let remaining = bytes[size..].to_vec();
+            if !<#ty as PacketVariable>::can_read(remaining.clone()) {
+                return 0;
+            }
+            let (#ident, field_size) = <#ty as PacketVariable>::from_packet(remaining);
+            size += field_size;
+        };
+    }
+
+    if let Some(len_ty) = &attrs.length {
+        if ty_is_vec(ty) {
+            let elem_ty = vec_element_ty(ty);
+            return quote! {
+                {
+                    let len_bytes = bytes[size..].to_vec();
+                    if !<#len_ty as PacketVariable>::can_read(len_bytes.clone()) {
+                        return 0;
+                    }
+                    let (len, len_size) = <#len_ty as PacketVariable>::from_packet(len_bytes);
+                    size += len_size;
+                    for _ in 0..len {
+                        let remaining = bytes[size..].to_vec();
+                        if !<#elem_ty as PacketVariable>::can_read(remaining.clone()) {
+                            return 0;
+                        }
+                        size += <#elem_ty as PacketVariable>::read_size(remaining);
+                    }
+                }
+            };
+        } else {
+            return quote! {
+                {
+                    let len_bytes = bytes[size..].to_vec();
+                    if !<#len_ty as PacketVariable>::can_read(len_bytes.clone()) {
+                        return 0;
+                    }
+                    let (len, len_size) = <#len_ty as PacketVariable>::from_packet(len_bytes);
+                    size += len_size + len as usize;
+                }
+            };
+        }
+    }
+
+    if let Some(count) = attrs.count {
+        let elem_ty = vec_element_ty(ty);
+        return quote! {
+            {
+                for _ in 0..#count {
+                    let remaining = bytes[size..].to_vec();
+                    if !<#elem_ty as PacketVariable>::can_read(remaining.clone()) {
+                        return 0;
+                    }
+                    size += <#elem_ty as PacketVariable>::read_size(remaining);
+                }
+            }
+        };
+    }
+
+    let cond = attrs.cond.as_ref().unwrap();
+    quote! {
+        if #cond {
+            let remaining = bytes[size..].to_vec();
+            if !<#ty as PacketVariable>::can_read(remaining.clone()) {
+                return 0;
+            }
+            size += <#ty as PacketVariable>::read_size(remaining);
+        }
+    }
+}
+
+/// Like [`field_size_stmt`], but for a field carrying its own `#[packet(endian = ...)]`.
+///
+/// Only a `String`'s length prefix is actually endian-sensitive here: the prefix is decoded with
+/// [`Endianness::from_packet_endian`] instead of the always-big-endian [`PacketVariable::read_size`],
+/// matching how [`field_endian_binding_stmt`]/[`field_endian_write_stmt`] read and write it. A
+/// fixed-width numeric field occupies the same number of bytes regardless of byte order, so its
+/// size still comes from the plain `PacketVariable` impl.
+fn field_endian_size_stmt(ty: &Ty, endian: EndianChoice) -> Tokens {
+    let endian_expr = endian.to_tokens();
+    if ty_is_string(ty) {
+        quote! {
+            {
+                let len_bytes = bytes[size..].to_vec();
+                if len_bytes.len() < 2 {
+                    return 0;
+                }
+                let (len, _) = <u16 as Endianness>::from_packet_endian(len_bytes[..2].to_vec(), #endian_expr);
+                size += 2 + len as usize;
+            }
+        }
+    } else {
+        quote! {
+            {
+                let remaining = bytes[size..].to_vec();
+                if !<#ty as PacketVariable>::can_read(remaining.clone()) {
+                    return 0;
+                }
+                size += <#ty as PacketVariable>::read_size(remaining);
+            }
+        }
+    }
+}
+
+fn impl_struct_derive(name: &Ident, fields: &Vec<Field>, struct_endian: Option<EndianChoice>) -> Tokens {
+    let attrs: Vec<FieldAttrs> = fields.iter().map(parse_field_attrs).collect();
+    let idents: Vec<&Ident> = fields.iter().map(| f | f.ident.as_ref().unwrap()).collect();
+    let types: Vec<&Ty> = fields.iter().map(| f | &f.ty).collect();
+    let cond_sources: HashSet<String> = attrs.iter().filter_map(| a | a.cond.as_ref().map(| c | c.to_string())).collect();
+
+    let endians: Vec<Option<EndianChoice>> = attrs.iter().map(| a | {
+        let has_other_attrs = a.skip || a.length.is_some() || a.count.is_some() || a.cond.is_some();
+        if a.endian.is_some() && has_other_attrs {
+            panic!("#[packet(endian = ...)] can't be combined with length/skip/count/if yet");
+        }
+        // A struct-level default is inherited only by fields that don't use another
+        // chunk0-3 attribute, so `#[packet(endian = "...")] struct Foo { ... }` stays usable
+        // alongside a `length`/`skip`/`count`/`if` field instead of panicking on them.
+        if has_other_attrs { None } else { a.endian.or(struct_endian) }
+    }).collect();
+
+    let from_stmts: Vec<Tokens> = idents.iter().zip(types.iter()).zip(attrs.iter()).zip(endians.iter())
+        .map(| (((ident, ty), attrs), endian) | match endian {
+            Some(endian) => field_endian_binding_stmt(ident, ty, *endian, false),
+            None => field_binding_stmt(ident, ty, attrs, false),
+        })
+        .collect();
+    let try_from_stmts: Vec<Tokens> = idents.iter().zip(types.iter()).zip(attrs.iter()).zip(endians.iter())
+        .map(| (((ident, ty), attrs), endian) | match endian {
+            Some(endian) => field_endian_binding_stmt(ident, ty, *endian, true),
+            None => field_binding_stmt(ident, ty, attrs, true),
+        })
+        .collect();
+    let to_stmts: Vec<Tokens> = idents.iter().zip(types.iter()).zip(attrs.iter()).zip(endians.iter())
+        .map(| (((ident, ty), attrs), endian) | match endian {
+            Some(endian) => field_endian_write_stmt(ident, ty, *endian),
+            None => field_write_stmt(ident, ty, attrs),
+        })
+        .collect();
+    let size_stmts: Vec<Tokens> = idents.iter().zip(types.iter()).zip(attrs.iter()).zip(endians.iter())
+        .map(| (((ident, ty), attrs), endian) | match endian {
+            Some(endian) => field_endian_size_stmt(ty, *endian),
+            None => field_size_stmt(ident, ty, attrs, &cond_sources),
+        })
+        .collect();
+
     quote! {
         impl PacketVariable for #name {
             fn from_packet(bytes: Vec<u8>) -> (Self, usize) where Self: Sized {
                 let mut packet = HPacket::from_header_id_and_bytes(0, bytes);
+                #(#from_stmts)*
                 (
-                    Self {
-                        #(
-                            #from_idents: packet.read()
-                        ),*
-                    },
+                    Self { #(#idents),* },
                     packet.read_index - 6
                 )
             }
 
             fn to_packet(&self) -> Vec<u8> {
                 let mut packet = HPacket::from_header_id(0);
-                #(
-                    packet.append(self.#to_idents.clone());
-                )*
+                #(#to_stmts)*
                 packet.get_bytes()[6..].to_vec()
             }
 
@@ -63,17 +562,270 @@ fn impl_struct_derive(name: &Ident, fields: &Vec<Field>) -> Tokens {
                 Self::read_size(bytes) != 0
             }
 
-            // TODO fix read_size
             fn read_size(bytes: Vec<u8>) -> usize {
                 let mut size = 0;
+                #(#size_stmts)*
+                size
+            }
+
+            fn try_from_packet(reader: &mut (impl Read + Seek)) -> Result<Self, PacketError> where Self: Sized {
+                #(#try_from_stmts)*
+                Ok(Self { #(#idents),* })
+            }
+        }
+    }
+}
+
+fn tuple_field_idents(count: usize) -> Vec<Ident> {
+    (0..count).map(| i | Ident::new(format!("field_{i}"))).collect()
+}
+
+fn impl_enum_derive(name: &Ident, variants: &Vec<syn::Variant>) -> Tokens {
+    if variants.len() > u8::max_value() as usize + 1 {
+        panic!("PacketVariable derive only supports enums with up to 256 variants");
+    }
+
+    let to_arms = variants.iter().enumerate().map(| (tag, variant) | {
+        let variant_ident = &variant.ident;
+        let tag = tag as u8;
+
+        match &variant.data {
+            VariantData::Unit => quote! {
+                #name::#variant_ident => {
+                    packet.append(#tag);
+                }
+            },
+            VariantData::Tuple(fields) => {
+                let binds = tuple_field_idents(fields.len());
+                quote! {
+                    #name::#variant_ident(#(ref #binds),*) => {
+                        packet.append(#tag);
+                        #(
+                            packet.append(#binds.clone());
+                        )*
+                    }
+                }
+            },
+            VariantData::Struct(fields) => {
+                let field_idents: Vec<&Ident> = fields.iter().map(| f | f.ident.as_ref().unwrap()).collect();
+                quote! {
+                    #name::#variant_ident { #(ref #field_idents),* } => {
+                        packet.append(#tag);
+                        #(
+                            packet.append(#field_idents.clone());
+                        )*
+                    }
+                }
+            },
+        }
+    });
+
+    let from_arms = variants.iter().enumerate().map(| (tag, variant) | {
+        let variant_ident = &variant.ident;
+        let tag = tag as u8;
+
+        match &variant.data {
+            VariantData::Unit => quote! {
+                #tag => #name::#variant_ident
+            },
+            VariantData::Tuple(fields) => {
+                let reads = fields.iter().map(| _ | quote! { packet.read() });
+                quote! {
+                    #tag => #name::#variant_ident(#(#reads),*)
+                }
+            },
+            VariantData::Struct(fields) => {
+                let field_idents: Vec<&Ident> = fields.iter().map(| f | f.ident.as_ref().unwrap()).collect();
+                quote! {
+                    #tag => #name::#variant_ident {
+                        #(
+                            #field_idents: packet.read()
+                        ),*
+                    }
+                }
+            },
+        }
+    });
+
+    let try_from_arms = variants.iter().enumerate().map(| (tag, variant) | {
+        let variant_ident = &variant.ident;
+        let tag = tag as u8;
+
+        match &variant.data {
+            VariantData::Unit => quote! {
+                #tag => #name::#variant_ident
+            },
+            VariantData::Tuple(fields) => {
+                let reads = fields.iter().map(| _ | quote! { PacketVariable::try_from_packet(reader)? });
+                quote! {
+                    #tag => #name::#variant_ident(#(#reads),*)
+                }
+            },
+            VariantData::Struct(fields) => {
+                let field_idents: Vec<&Ident> = fields.iter().map(| f | f.ident.as_ref().unwrap()).collect();
+                quote! {
+                    #tag => #name::#variant_ident {
+                        #(
+                            #field_idents: PacketVariable::try_from_packet(reader)?
+                        ),*
+                    }
+                }
+            },
+        }
+    });
+
+    let size_arms = variants.iter().enumerate().map(| (tag, variant) | {
+        let tag = tag as u8;
+        let types: Vec<&Ty> = match &variant.data {
+            VariantData::Unit => Vec::new(),
+            VariantData::Tuple(fields) => fields.iter().map(| f | &f.ty).collect(),
+            VariantData::Struct(fields) => fields.iter().map(| f | &f.ty).collect(),
+        };
+
+        quote! {
+            #tag => {
                 #(
                     {
-                        println!("#types");
-                        size += 1;
+                        let remaining = bytes[size..].to_vec();
+                        if !<#types as PacketVariable>::can_read(remaining.clone()) {
+                            return 0;
+                        }
+                        size += <#types as PacketVariable>::read_size(remaining.clone());
                     }
                 )*
-                1
             }
         }
+    });
+
+    quote! {
+        impl PacketVariable for #name {
+            fn from_packet(bytes: Vec<u8>) -> (Self, usize) where Self: Sized {
+                let mut packet = HPacket::from_header_id_and_bytes(0, bytes);
+                let tag: u8 = packet.read();
+
+                let value = match tag {
+                    #(#from_arms),*,
+                    _ => panic!("Unknown discriminant {} for enum", tag),
+                };
+
+                (value, packet.read_index - 6)
+            }
+
+            fn to_packet(&self) -> Vec<u8> {
+                let mut packet = HPacket::from_header_id(0);
+
+                match self {
+                    #(#to_arms)*
+                }
+
+                packet.get_bytes()[6..].to_vec()
+            }
+
+            fn can_read(bytes: Vec<u8>) -> bool {
+                Self::read_size(bytes) != 0
+            }
+
+            fn read_size(bytes: Vec<u8>) -> usize {
+                if !u8::can_read(bytes.clone()) {
+                    return 0;
+                }
+
+                let tag = bytes[0];
+                let mut size = 1;
+
+                match tag {
+                    #(#size_arms)*
+                    _ => return 0,
+                }
+
+                size
+            }
+
+            fn try_from_packet(reader: &mut (impl Read + Seek)) -> Result<Self, PacketError> where Self: Sized {
+                let tag: u8 = PacketVariable::try_from_packet(reader)?;
+
+                Ok(match tag {
+                    #(#try_from_arms),*,
+                    _ => return Err(PacketError::UnknownDiscriminant(tag)),
+                })
+            }
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endian_choice_parses_known_aliases() {
+        assert!(matches!(EndianChoice::from_str("be"), EndianChoice::Big));
+        assert!(matches!(EndianChoice::from_str("big"), EndianChoice::Big));
+        assert!(matches!(EndianChoice::from_str("le"), EndianChoice::Little));
+        assert!(matches!(EndianChoice::from_str("little"), EndianChoice::Little));
+        assert!(matches!(EndianChoice::from_str("default"), EndianChoice::Runtime));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unsupported")]
+    fn endian_choice_rejects_unknown_values() {
+        EndianChoice::from_str("middle");
+    }
+
+    fn fields_of(src: &str) -> Vec<Field> {
+        match syn::parse_derive_input(src).unwrap().body {
+            Body::Struct(VariantData::Struct(fields)) => fields,
+            _ => panic!("expected a struct with named fields"),
+        }
+    }
+
+    #[test]
+    fn parse_field_attrs_reads_length() {
+        let fields = fields_of(r#"struct S { #[packet(length = "u8")] name: String }"#);
+        let attrs = parse_field_attrs(&fields[0]);
+        assert_eq!(attrs.length.map(| i | i.to_string()), Some("u8".to_string()));
+    }
+
+    #[test]
+    fn parse_field_attrs_reads_skip() {
+        let fields = fields_of("struct S { #[packet(skip)] padding: u8 }");
+        assert!(parse_field_attrs(&fields[0]).skip);
+    }
+
+    #[test]
+    fn parse_field_attrs_reads_count() {
+        let fields = fields_of("struct S { #[packet(count = 4)] items: Vec<u8> }");
+        assert_eq!(parse_field_attrs(&fields[0]).count, Some(4));
+    }
+
+    #[test]
+    fn parse_field_attrs_reads_if_with_default() {
+        let fields = fields_of(
+            r#"struct S { has_extra: bool, #[packet(if = "has_extra", default)] extra: u8 }"#,
+        );
+        let attrs = parse_field_attrs(&fields[1]);
+        assert_eq!(attrs.cond.map(| i | i.to_string()), Some("has_extra".to_string()));
+        assert!(attrs.default);
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[should_panic(expected = "must be paired with")]
+    fn parse_field_attrs_rejects_if_without_default() {
+        let fields = fields_of(r#"struct S { has_extra: bool, #[packet(if = "has_extra")] extra: u8 }"#);
+        parse_field_attrs(&fields[1]);
+    }
+
+    #[test]
+    fn struct_level_endian_does_not_panic_alongside_a_length_field() {
+        // Regression test: a struct-level #[packet(endian = ...)] used to be force-inherited
+        // onto every field, then panic the moment that field also carried length/skip/count/if.
+        let ast = syn::parse_derive_input(
+            r#"#[packet(endian = "le")] struct S { a: u16, #[packet(length = "u8")] b: String }"#,
+        ).unwrap();
+        let generated = impl_packet_variable(&ast).to_string();
+
+        // `a` still goes through the endian-aware path...
+        assert!(generated.contains("Endianness"));
+        // ...while `b` keeps using its own length-prefix attribute, untouched by the struct default.
+        assert!(generated.contains("len_bytes"));
+    }
+}
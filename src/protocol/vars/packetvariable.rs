@@ -1,11 +1,73 @@
 use core::mem::size_of;
 use std::collections::HashMap;
 use std::error::Error;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::hash::Hash;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicU8, Ordering};
 use crate::protocol::hpacket::HPacket;
 use crate::protocol::vars::legacy::{LegacyId, LegacyLength};
 
+/// Byte order used to encode/decode a primitive or `String` length prefix on the wire.
+///
+/// The runtime default is [`Endian::Big`], matching Habbo's big-endian framing; call
+/// [`Endian::set_default`] once at startup to switch every `#[packet(endian = "default")]`
+/// struct or field (e.g. to target a little-endian protocol such as PSO) at once. Fields
+/// without any `endian` attribute, and plain [`PacketVariable`] reads and writes, are always
+/// big-endian and do not follow this default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Big,
+    Little,
+}
+
+static DEFAULT_ENDIAN: AtomicU8 = AtomicU8::new(0);
+
+impl Endian {
+    /// The process-wide default used by `#[packet(endian = "default")]` structs and fields.
+    pub fn default_endian() -> Endian {
+        match DEFAULT_ENDIAN.load(Ordering::Relaxed) {
+            0 => Endian::Big,
+            _ => Endian::Little,
+        }
+    }
+
+    /// Overrides the process-wide default returned by [`Endian::default_endian`].
+    pub fn set_default(endian: Endian) {
+        let value = match endian {
+            Endian::Big => 0,
+            Endian::Little => 1,
+        };
+        DEFAULT_ENDIAN.store(value, Ordering::Relaxed);
+    }
+}
+
+/// Errors produced while reading a `PacketVariable` from a cursor via [`PacketVariable::try_from_packet`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PacketError {
+    /// The reader ran out of bytes before a full value could be read.
+    NotEnoughBytes,
+    /// A `String` field did not contain valid UTF-8.
+    InvalidUtf8,
+    /// An enum discriminant did not match any known variant.
+    UnknownDiscriminant(u8),
+    /// A length prefix did not fit into the target integer type.
+    LengthOverflow,
+}
+
+impl Display for PacketError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PacketError::NotEnoughBytes => write!(f, "not enough bytes left to read"),
+            PacketError::InvalidUtf8 => write!(f, "string was not valid UTF-8"),
+            PacketError::UnknownDiscriminant(tag) => write!(f, "unknown discriminant {tag}"),
+            PacketError::LengthOverflow => write!(f, "length prefix overflowed"),
+        }
+    }
+}
+
+impl Error for PacketError {}
+
 pub trait PacketVariable {
     /// Reads a variable from the beginning of the given bytes vector
     ///
@@ -16,6 +78,41 @@ pub trait PacketVariable {
     fn to_packet(&self) -> Vec<u8>;
     fn can_read(bytes: Vec<u8>) -> bool;
     fn read_size(bytes: Vec<u8>) -> usize;
+
+    /// Reads a variable straight off a cursor, advancing it past the bytes it consumed.
+    ///
+    /// Unlike [`PacketVariable::from_packet`], the overrides in this file never clone the
+    /// remaining buffer, and truncated/malformed input is reported as a [`PacketError`].
+    ///
+    /// The default implementation is a shim over [`PacketVariable::from_packet`]/[`PacketVariable::can_read`]
+    /// for implementors that predate this method (e.g. [`LegacyLength`]/[`LegacyId`]): it buffers the
+    /// rest of the reader, decodes from the front of that buffer, then seeks back over whatever it
+    /// over-read so the cursor ends up exactly past the consumed bytes. Types in this file override
+    /// it with a direct, non-buffering read.
+    fn try_from_packet(reader: &mut (impl Read + Seek)) -> Result<Self, PacketError> where Self: Sized {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).map_err(|_| PacketError::NotEnoughBytes)?;
+
+        if !Self::can_read(buf.clone()) {
+            return Err(PacketError::NotEnoughBytes);
+        }
+
+        let (value, consumed) = Self::from_packet(buf.clone());
+        let overread = (buf.len() - consumed) as i64;
+        reader.seek(SeekFrom::Current(-overread)).map_err(|_| PacketError::NotEnoughBytes)?;
+        Ok(value)
+    }
+}
+
+/// Endian-aware encoding for the primitive integer/float types and `String` length prefixes.
+///
+/// Only these implement it: composite types (`Vec`, `HashMap`, tuples, ...) always nest their
+/// element reads/writes through [`PacketVariable`], which stays fixed at big-endian; use this
+/// trait (via the derive's `#[packet(endian = "...")]`) on the individual fields that actually
+/// need a different byte order.
+pub trait Endianness: PacketVariable {
+    fn from_packet_endian(bytes: Vec<u8>, endian: Endian) -> (Self, usize) where Self: Sized;
+    fn to_packet_endian(&self, endian: Endian) -> Vec<u8>;
 }
 
 fn to_sized_array<T: Clone + Debug, const N: usize>(v: Vec<T>) -> [T; N] {
@@ -41,6 +138,30 @@ macro_rules! impl_packet_variable {
             fn read_size(bytes: Vec<u8>) -> usize {
                 size_of::<$ty>()
             }
+
+            fn try_from_packet(reader: &mut (impl Read + Seek)) -> Result<Self, PacketError> {
+                let mut buf = [0u8; size_of::<$ty>()];
+                reader.read_exact(&mut buf).map_err(|_| PacketError::NotEnoughBytes)?;
+                Ok(Self::from_be_bytes(buf))
+            }
+        }
+
+        impl Endianness for $ty {
+            fn from_packet_endian(bytes: Vec<u8>, endian: Endian) -> (Self, usize) {
+                let bytes_array: [u8; size_of::<$ty>()] = to_sized_array(bytes);
+                let value = match endian {
+                    Endian::Big => Self::from_be_bytes(bytes_array),
+                    Endian::Little => Self::from_le_bytes(bytes_array),
+                };
+                (value, size_of::<$ty>())
+            }
+
+            fn to_packet_endian(&self, endian: Endian) -> Vec<u8> {
+                match endian {
+                    Endian::Big => self.to_be_bytes().to_vec(),
+                    Endian::Little => self.to_le_bytes().to_vec(),
+                }
+            }
         }
     )+)
 }
@@ -63,6 +184,12 @@ impl PacketVariable for bool {
     fn read_size(bytes: Vec<u8>) -> usize {
         1
     }
+
+    fn try_from_packet(reader: &mut (impl Read + Seek)) -> Result<Self, PacketError> {
+        let mut buf = [0u8; 1];
+        reader.read_exact(&mut buf).map_err(|_| PacketError::NotEnoughBytes)?;
+        Ok(buf[0] != 0)
+    }
 }
 
 impl PacketVariable for String {
@@ -91,6 +218,29 @@ impl PacketVariable for String {
             2 + u16::from_packet(bytes.clone()).0 as usize
         }
     }
+
+    fn try_from_packet(reader: &mut (impl Read + Seek)) -> Result<Self, PacketError> {
+        let len = u16::try_from_packet(reader)? as usize;
+        let mut buf = vec![0u8; len];
+        reader.read_exact(&mut buf).map_err(|_| PacketError::NotEnoughBytes)?;
+        String::from_utf8(buf).map_err(|_| PacketError::InvalidUtf8)
+    }
+}
+
+impl Endianness for String {
+    fn from_packet_endian(bytes: Vec<u8>, endian: Endian) -> (Self, usize) {
+        let s_size = u16::from_packet_endian(bytes.clone(), endian).0 as usize;
+        let s = String::from_utf8(bytes[2..2 + s_size].to_vec()).expect("Couldn't read string");
+        (s, 2 + s_size)
+    }
+
+    fn to_packet_endian(&self, endian: Endian) -> Vec<u8> {
+        let bytes = self.as_bytes();
+        let len = bytes.len() as u16;
+        let mut res = len.to_packet_endian(endian);
+        res.extend(bytes);
+        res
+    }
 }
 
 impl<T: PacketVariable + Clone> PacketVariable for Vec<T> {
@@ -138,6 +288,15 @@ impl<T: PacketVariable + Clone> PacketVariable for Vec<T> {
             size
         }
     }
+
+    fn try_from_packet(reader: &mut (impl Read + Seek)) -> Result<Self, PacketError> {
+        let len = LegacyLength::try_from_packet(reader)?;
+        let mut res: Vec<T> = Vec::new();
+        for _ in 0..*len {
+            res.push(T::try_from_packet(reader)?);
+        }
+        Ok(res)
+    }
 }
 
 impl<K: PacketVariable + Clone + Eq + Hash, V: PacketVariable + Clone + Eq + Hash> PacketVariable for HashMap<K, V> {
@@ -192,6 +351,17 @@ impl<K: PacketVariable + Clone + Eq + Hash, V: PacketVariable + Clone + Eq + Has
             size
         }
     }
+
+    fn try_from_packet(reader: &mut (impl Read + Seek)) -> Result<Self, PacketError> {
+        let len = LegacyLength::try_from_packet(reader)?;
+        let mut res: HashMap<K, V> = HashMap::new();
+        for _ in 0..*len {
+            let key = K::try_from_packet(reader)?;
+            let value = V::try_from_packet(reader)?;
+            res.insert(key, value);
+        }
+        Ok(res)
+    }
 }
 
 macro_rules! impl_packet_tuple_variable {
@@ -230,6 +400,14 @@ macro_rules! impl_packet_tuple_variable {
                 )+
                 size
             }
+
+            fn try_from_packet(reader: &mut (impl Read + Seek)) -> Result<Self, PacketError> {
+                Ok((
+                    $(
+                        $ty::try_from_packet(reader)?
+                    ),+
+                ))
+            }
         }
     )+)
 }
@@ -293,6 +471,14 @@ macro_rules! impl_packet_array_variable {
                 }
                 size
             }
+
+            fn try_from_packet(reader: &mut (impl Read + Seek)) -> Result<Self, PacketError> {
+                let mut res: Vec<T> = Vec::new();
+                for _ in 0..$size {
+                    res.push(T::try_from_packet(reader)?);
+                }
+                Ok(to_sized_array::<T, $size>(res))
+            }
         }
     )+)
 }
@@ -329,4 +515,106 @@ impl<T: PacketVariable> PacketVariable for Option<T> {
     fn can_read(bytes: Vec<u8>) -> bool {
         true
     }
+
+    fn try_from_packet(reader: &mut (impl Read + Seek)) -> Result<Self, PacketError> {
+        let start = reader.stream_position().map_err(|_| PacketError::NotEnoughBytes)?;
+        match T::try_from_packet(reader) {
+            Ok(val) => Ok(Some(val)),
+            Err(PacketError::NotEnoughBytes) => {
+                // `T` may have read (and dropped) several sub-values before truncating; rewind
+                // the cursor to where we started so later fields don't decode from mid-field.
+                reader.seek(SeekFrom::Start(start)).map_err(|_| PacketError::NotEnoughBytes)?;
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// A `PacketVariable` wrapper around bytes that are already encoded and should be appended as-is.
+///
+/// `#[packet(endian = "...")]` fields encode through [`Endianness::to_packet_endian`] rather than
+/// the big-endian-only [`PacketVariable::to_packet`], so they can't hand their bytes to
+/// `packet.append` directly; wrapping the already-encoded bytes in `RawBytes` lets them reuse
+/// `append`'s length-header bookkeeping instead of poking the packet's buffer by hand.
+pub struct RawBytes(pub Vec<u8>);
+
+impl PacketVariable for RawBytes {
+    fn from_packet(bytes: Vec<u8>) -> (Self, usize) where Self: Sized {
+        let len = bytes.len();
+        (RawBytes(bytes), len)
+    }
+
+    fn to_packet(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+
+    fn can_read(_bytes: Vec<u8>) -> bool {
+        true
+    }
+
+    fn read_size(bytes: Vec<u8>) -> usize {
+        bytes.len()
+    }
+
+    fn try_from_packet(reader: &mut (impl Read + Seek)) -> Result<Self, PacketError> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).map_err(|_| PacketError::NotEnoughBytes)?;
+        Ok(RawBytes(buf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn try_from_packet_decodes_primitives() {
+        let mut reader = Cursor::new(vec![0x00, 0x00, 0x01, 0x00]);
+        assert_eq!(u32::try_from_packet(&mut reader), Ok(256));
+    }
+
+    #[test]
+    fn try_from_packet_reports_truncated_primitives() {
+        let mut reader = Cursor::new(vec![0x00, 0x01]);
+        assert_eq!(u32::try_from_packet(&mut reader), Err(PacketError::NotEnoughBytes));
+    }
+
+    #[test]
+    fn try_from_packet_decodes_strings() {
+        let mut reader = Cursor::new(vec![0x00, 0x02, b'h', b'i']);
+        assert_eq!(String::try_from_packet(&mut reader), Ok("hi".to_string()));
+    }
+
+    #[test]
+    fn try_from_packet_decodes_tuples() {
+        let mut reader = Cursor::new(vec![0x01, 0x00, 0x02]);
+        assert_eq!(<(u8, u16)>::try_from_packet(&mut reader), Ok((1u8, 2u16)));
+    }
+
+    #[test]
+    fn option_try_from_packet_returns_value_when_present() {
+        let mut reader = Cursor::new(vec![0x00, 0x00, 0x01, 0x00]);
+        assert_eq!(Option::<u32>::try_from_packet(&mut reader), Ok(Some(256)));
+    }
+
+    #[test]
+    fn option_try_from_packet_rewinds_on_truncated_input() {
+        // Only 2 of the 4 bytes a u32 needs: the inner read must fail and the
+        // cursor must end up back where it started, not mid-field.
+        let mut reader = Cursor::new(vec![0x00, 0x01]);
+        assert_eq!(Option::<u32>::try_from_packet(&mut reader), Ok(None));
+        assert_eq!(reader.stream_position().unwrap(), 0);
+    }
+
+    #[test]
+    fn option_try_from_packet_does_not_desync_a_composite_value() {
+        // A (u16, u16) reads its first half successfully, then truncates on
+        // the second: the whole read must be rewound, not just the failing
+        // sub-field, so a following read on the same cursor sees the original bytes.
+        let mut reader = Cursor::new(vec![0x00, 0x01, 0x00]);
+        assert_eq!(Option::<(u16, u16)>::try_from_packet(&mut reader), Ok(None));
+        assert_eq!(u16::try_from_packet(&mut reader), Ok(1));
+    }
 }
\ No newline at end of file